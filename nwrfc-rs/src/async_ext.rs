@@ -0,0 +1,41 @@
+//! Async equivalents of the blocking connection and invoke APIs, gated behind the
+//! `async` feature for services built on Tokio.
+use crate::{connection::ping_handle, error::Result, function::RfcFunction, RfcConnection, RfcConnectionBuilder};
+
+impl RfcConnectionBuilder {
+    /// Async equivalent of [`build`](Self::build): opens the connection on a blocking
+    /// worker thread so the calling task isn't blocked on the underlying
+    /// `RfcOpenConnection` FFI call.
+    pub async fn build_async(self) -> Result<RfcConnection> {
+        tokio::task::spawn_blocking(move || self.build())
+            .await
+            .expect("connection worker thread panicked")
+    }
+}
+
+impl RfcConnection {
+    /// Async equivalent of [`ping`](Self::ping).
+    pub async fn ping_async(&self) -> Result<()> {
+        let inner = self.clone_inner();
+        tokio::task::spawn_blocking(move || ping_handle(*inner.handle.lock().unwrap()))
+            .await
+            .expect("ping worker thread panicked")
+    }
+}
+
+impl<'conn> RfcFunction<'conn> {
+    /// Async equivalent of [`invoke`](RfcFunction::invoke).
+    ///
+    /// `RfcFunction` borrows its connection's handle, so it isn't `'static` and can't be
+    /// moved onto a [`spawn_blocking`](tokio::task::spawn_blocking) worker thread like
+    /// [`RfcConnectionBuilder::build_async`] does. Instead this runs the blocking
+    /// `RfcInvoke` FFI call via [`tokio::task::block_in_place`], which hands the current
+    /// OS thread off to another worker for the duration of the call. Taking `&mut self`
+    /// means the borrow checker — not just documentation — rules out two tasks calling
+    /// this on the same `RfcFunction` at once.
+    ///
+    /// Requires a multi-threaded Tokio runtime.
+    pub async fn invoke_async(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| self.invoke())
+    }
+}
@@ -0,0 +1,147 @@
+use crate::connection::RfcConnection;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A generic RFC client, abstracting over [`RfcConnection`] so downstream code can be
+/// written generically and unit-tested with [`MockRfcConnection`] instead of a live SAP
+/// system.
+pub trait RfcClient {
+    /// Check if the connection is alive.
+    fn ping(&self) -> Result<()>;
+
+    /// Invoke a remote enabled function module, setting `imports` by parameter name and
+    /// reading back the named `exports`.
+    fn invoke(
+        &self,
+        function: &str,
+        imports: &HashMap<String, String>,
+        exports: &[&str],
+    ) -> Result<HashMap<String, String>>;
+}
+
+impl RfcClient for RfcConnection {
+    fn ping(&self) -> Result<()> {
+        RfcConnection::ping(self)
+    }
+
+    fn invoke(
+        &self,
+        function: &str,
+        imports: &HashMap<String, String>,
+        exports: &[&str],
+    ) -> Result<HashMap<String, String>> {
+        let func = self.get_function(function)?;
+
+        for (name, value) in imports {
+            func.get_parameter(name)?.set_string(value)?;
+        }
+
+        func.invoke()?;
+
+        exports
+            .iter()
+            .map(|&name| Ok((name.to_owned(), func.get_parameter(name)?.get_string()?)))
+            .collect()
+    }
+}
+
+type Handler = Box<dyn Fn(&HashMap<String, String>) -> Result<HashMap<String, String>> + Send>;
+
+/// An in-memory [`RfcClient`] backed by canned function handlers, for unit-testing code
+/// written against the trait without the proprietary NW RFC SDK present.
+#[derive(Default)]
+pub struct MockRfcConnection {
+    handlers: Mutex<HashMap<String, Handler>>,
+}
+
+impl MockRfcConnection {
+    /// Get a new mock connection with no registered function modules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler for a function module name, invoked with the import parameters
+    /// an [`invoke`](RfcClient::invoke) call passes for that name.
+    ///
+    /// Invoking a function module with no registered handler panics, same as calling an
+    /// unexpected method on a mock would.
+    pub fn on_invoke<F>(&self, function: &str, handler: F)
+    where
+        F: Fn(&HashMap<String, String>) -> Result<HashMap<String, String>> + Send + 'static,
+    {
+        self.handlers
+            .lock()
+            .unwrap()
+            .insert(function.to_owned(), Box::new(handler));
+    }
+}
+
+impl RfcClient for MockRfcConnection {
+    fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn invoke(
+        &self,
+        function: &str,
+        imports: &HashMap<String, String>,
+        exports: &[&str],
+    ) -> Result<HashMap<String, String>> {
+        let handlers = self.handlers.lock().unwrap();
+        let handler = handlers
+            .get(function)
+            .unwrap_or_else(|| panic!("no handler registered for `{}`", function));
+
+        let result = handler(imports)?;
+        exports
+            .iter()
+            .map(|&name| {
+                let value = result
+                    .get(name)
+                    .unwrap_or_else(|| panic!("handler for `{}` did not set export `{}`", function, name));
+                Ok((name.to_owned(), value.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_passes_imports_to_handler_and_returns_scripted_exports() {
+        let mock = MockRfcConnection::new();
+        mock.on_invoke("SCP_STRING_ECHO", |imports| {
+            let mut exports = HashMap::new();
+            exports.insert("EXP".to_owned(), imports["IMP"].clone());
+            Ok(exports)
+        });
+
+        let mut imports = HashMap::new();
+        imports.insert("IMP".to_owned(), "Test String".to_owned());
+
+        let exports = mock
+            .invoke("SCP_STRING_ECHO", &imports, &["EXP"])
+            .unwrap();
+
+        assert_eq!(exports["EXP"], "Test String");
+    }
+
+    #[test]
+    #[should_panic(expected = "no handler registered")]
+    fn mock_panics_on_unregistered_function() {
+        let mock = MockRfcConnection::new();
+        let _ = mock.invoke("UNKNOWN", &HashMap::new(), &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not set export `EXP`")]
+    fn mock_panics_on_missing_export() {
+        let mock = MockRfcConnection::new();
+        mock.on_invoke("SCP_STRING_ECHO", |_| Ok(HashMap::new()));
+
+        let _ = mock.invoke("SCP_STRING_ECHO", &HashMap::new(), &["EXP"]);
+    }
+}
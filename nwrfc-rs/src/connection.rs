@@ -8,36 +8,75 @@ use sapnwrfc_sys::{
     self, RfcCloseConnection, RfcCreateFunction, RfcGetFunctionDesc, RfcOpenConnection, RfcPing,
     SAP_UC,
 };
-use std::{collections::HashMap, ptr};
+use std::{
+    collections::HashMap,
+    ptr,
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+type ConnParams = Vec<(Vec<SAP_UC>, Vec<SAP_UC>)>;
 
 /// An SAP NW RFC connection.
 #[derive(Debug)]
 pub struct RfcConnection {
-    handle: sapnwrfc_sys::RFC_CONNECTION_HANDLE,
+    inner: Arc<ConnectionInner>,
+    keepalive: Option<KeepaliveWorker>,
 }
 
-impl RfcConnection {
-    pub(crate) fn new(params: Vec<(Vec<SAP_UC>, Vec<SAP_UC>)>) -> Result<RfcConnection> {
-        let conn_params: Vec<_> = params
-            .iter()
-            .map(|(k, v)| sapnwrfc_sys::RFC_CONNECTION_PARAMETER {
-                name: k.as_ptr(),
-                value: v.as_ptr(),
-            })
-            .collect();
+/// The connection state shared between an [`RfcConnection`] and its keepalive worker.
+///
+/// The handle is kept behind a mutex so a reconnect can swap it out from the keepalive
+/// thread while `get_function`/`ping`/`invoke` observe at most one in-flight reconnect.
+#[derive(Debug)]
+pub(crate) struct ConnectionInner {
+    pub(crate) handle: Mutex<sapnwrfc_sys::RFC_CONNECTION_HANDLE>,
+    params: ConnParams,
+}
 
-        let mut err_info = RfcErrorInfo::new();
-        let handle = unsafe {
-            RfcOpenConnection(
-                conn_params.as_ptr(),
-                conn_params.len() as u32,
-                err_info.as_mut_ptr(),
-            )
-        };
-        if handle.is_null() {
-            return Err(err_info);
+// Safety: `handle` is a raw NW RFC handle that is only ever read or written through the
+// mutex above, so it's sound to share `ConnectionInner` across threads.
+unsafe impl Send for ConnectionInner {}
+unsafe impl Sync for ConnectionInner {}
+
+impl Drop for ConnectionInner {
+    fn drop(&mut self) {
+        // Runs when the last `Arc<ConnectionInner>` goes away, whether that's the owning
+        // `RfcConnection` or a clone held by an in-flight `ping_async`, so the handle is
+        // closed exactly once regardless of who drops last.
+        let mut handle = self.handle.lock().unwrap();
+        if !handle.is_null() {
+            let mut err_info = RfcErrorInfo::new();
+            unsafe {
+                if is_rc_err!(RfcCloseConnection(*handle, err_info.as_mut_ptr())) {
+                    log::warn!("Connection close failed: {}", err_info);
+                }
+            }
+            *handle = ptr::null_mut();
         }
-        Ok(Self { handle })
+    }
+}
+
+impl RfcConnection {
+    pub(crate) fn new(
+        params: ConnParams,
+        keepalive: Option<Duration>,
+        auto_reconnect: bool,
+        on_reconnect: Option<Arc<dyn Fn() + Send + Sync>>,
+    ) -> Result<RfcConnection> {
+        let handle = open_handle(&params)?;
+
+        let inner = Arc::new(ConnectionInner {
+            handle: Mutex::new(handle),
+            params,
+        });
+
+        let keepalive = keepalive.map(|interval| {
+            KeepaliveWorker::spawn(Arc::clone(&inner), interval, auto_reconnect, on_reconnect)
+        });
+
+        Ok(Self { inner, keepalive })
     }
 
     /// Get an empty connection builder to provide parameters for connecting.
@@ -49,24 +88,33 @@ impl RfcConnection {
     ///
     /// Equivalent to only setting the `dest` parameter in a connection builder.
     pub fn for_dest(name: &str) -> Result<RfcConnection> {
-        Self::new(vec![(uc::from_str("dest")?, uc::from_str(name)?)])
+        Self::new(
+            vec![(uc::from_str("dest")?, uc::from_str(name)?)],
+            None,
+            false,
+            None,
+        )
     }
 
     /// Check if the connection is alive by sending an RFC ping.
     pub fn ping(&self) -> Result<()> {
-        unsafe {
-            check_rc_ok!(RfcPing(self.handle));
-        }
-        Ok(())
+        ping_handle(*self.inner.handle.lock().unwrap())
+    }
+
+    /// Clone the shared, `'static` handle state, for use by code (e.g. the `async`
+    /// feature) that needs to move it onto another thread without borrowing `self`.
+    pub(crate) fn clone_inner(&self) -> Arc<ConnectionInner> {
+        Arc::clone(&self.inner)
     }
 
     /// Get a remote enabled function module by name.
     pub fn get_function<'conn>(&'conn self, name: &str) -> Result<RfcFunction<'conn>> {
         let uc_name = uc::from_str(name)?;
+        let handle = self.inner.handle.lock().unwrap();
 
         let mut err_info = RfcErrorInfo::new();
         let desc_handle =
-            unsafe { RfcGetFunctionDesc(self.handle, uc_name.as_ptr(), err_info.as_mut_ptr()) };
+            unsafe { RfcGetFunctionDesc(*handle, uc_name.as_ptr(), err_info.as_mut_ptr()) };
         if desc_handle.is_null() {
             return Err(err_info);
         }
@@ -74,30 +122,178 @@ impl RfcConnection {
         if func_handle.is_null() {
             return Err(err_info);
         }
-        Ok(RfcFunction::new(&self.handle, desc_handle, func_handle))
+        Ok(RfcFunction::new(&self.inner.handle, desc_handle, func_handle))
     }
 }
 
 unsafe impl Send for RfcConnection {}
+unsafe impl Sync for RfcConnection {}
 
 impl Drop for RfcConnection {
     fn drop(&mut self) {
-        if !self.handle.is_null() {
-            let mut err_info = RfcErrorInfo::new();
-            unsafe {
-                if is_rc_err!(RfcCloseConnection(self.handle, err_info.as_mut_ptr())) {
-                    log::warn!("Connection close failed: {}", err_info);
+        // Stop the keepalive thread before `inner` is dropped below, so it isn't left
+        // pinging a handle that's about to be (or already being) closed.
+        if let Some(keepalive) = self.keepalive.take() {
+            keepalive.stop();
+        }
+    }
+}
+
+fn open_handle(params: &ConnParams) -> Result<sapnwrfc_sys::RFC_CONNECTION_HANDLE> {
+    let conn_params: Vec<_> = params
+        .iter()
+        .map(|(k, v)| sapnwrfc_sys::RFC_CONNECTION_PARAMETER {
+            name: k.as_ptr(),
+            value: v.as_ptr(),
+        })
+        .collect();
+
+    let mut err_info = RfcErrorInfo::new();
+    let handle = unsafe {
+        RfcOpenConnection(
+            conn_params.as_ptr(),
+            conn_params.len() as u32,
+            err_info.as_mut_ptr(),
+        )
+    };
+    if handle.is_null() {
+        return Err(err_info);
+    }
+    Ok(handle)
+}
+
+pub(crate) fn ping_handle(handle: sapnwrfc_sys::RFC_CONNECTION_HANDLE) -> Result<()> {
+    unsafe {
+        check_rc_ok!(RfcPing(handle));
+    }
+    Ok(())
+}
+
+/// Background worker that pings a connection at a fixed interval and, if `auto_reconnect`
+/// is enabled, transparently reopens it when a ping fails.
+struct KeepaliveWorker {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl KeepaliveWorker {
+    fn spawn(
+        inner: Arc<ConnectionInner>,
+        interval: Duration,
+        auto_reconnect: bool,
+        on_reconnect: Option<Arc<dyn Fn() + Send + Sync>>,
+    ) -> Self {
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let stop_wait = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            let (lock, condvar) = &*stop_wait;
+            let mut stopped = lock.lock().unwrap();
+            loop {
+                let (guard, timeout) = condvar.wait_timeout(stopped, interval).unwrap();
+                stopped = guard;
+                if *stopped {
+                    break;
+                }
+                if !timeout.timed_out() {
+                    // Woken without timing out and without being told to stop: a spurious
+                    // wakeup, so just go back to waiting, starting a full interval over.
+                    continue;
+                }
+
+                let handle = inner.handle.lock().unwrap();
+                let ok = ping_handle(*handle).is_ok();
+                drop(handle);
+                if ok {
+                    continue;
+                }
+
+                if !auto_reconnect {
+                    log::warn!("Keepalive ping failed and auto-reconnect is disabled");
+                    continue;
+                }
+
+                let mut handle = inner.handle.lock().unwrap();
+                if !handle.is_null() {
+                    let mut err_info = RfcErrorInfo::new();
+                    unsafe {
+                        if is_rc_err!(RfcCloseConnection(*handle, err_info.as_mut_ptr())) {
+                            log::warn!("Failed to close stale connection handle: {}", err_info);
+                        }
+                    }
+                    *handle = ptr::null_mut();
+                }
+
+                match open_handle(&inner.params) {
+                    Ok(new_handle) => {
+                        *handle = new_handle;
+                        drop(handle);
+                        log::info!("Reconnected after a failed keepalive ping");
+                        if let Some(callback) = &on_reconnect {
+                            callback();
+                        }
+                    }
+                    // Leave the handle null: a future ping/reconnect attempt will see it's
+                    // already closed instead of double-closing or pinging a stale pointer.
+                    Err(err) => log::warn!("Reconnect attempt failed: {}", err),
                 }
             }
-            self.handle = ptr::null_mut();
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
         }
     }
+
+    /// Signal the worker to stop and wait for it to exit. Wakes the worker immediately
+    /// rather than waiting out the rest of its current keepalive interval.
+    fn stop(mut self) {
+        let (lock, condvar) = &*self.stop;
+        *lock.lock().unwrap() = true;
+        condvar.notify_one();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for KeepaliveWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeepaliveWorker").finish_non_exhaustive()
+    }
+}
+
+/// Error from [`RfcConnectionBuilder::from_file`] when the config file can't be read.
+#[derive(Debug)]
+pub struct ConfigError(std::io::Error);
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to read connection config: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        Self(err)
+    }
 }
 
 /// An RFC connection builder to prepare parameters for opening the connection.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct RfcConnectionBuilder {
     params: HashMap<String, String>,
+    keepalive: Option<Duration>,
+    auto_reconnect: bool,
+    on_reconnect: Option<Arc<dyn Fn() + Send + Sync>>,
 }
 
 impl RfcConnectionBuilder {
@@ -105,6 +301,9 @@ impl RfcConnectionBuilder {
     pub fn new() -> Self {
         Self {
             params: HashMap::new(),
+            keepalive: None,
+            auto_reconnect: false,
+            on_reconnect: None,
         }
     }
 
@@ -120,14 +319,72 @@ impl RfcConnectionBuilder {
         self
     }
 
+    /// Get a new builder whose parameters are exactly the given key/value map, e.g. one
+    /// parsed from TOML or JSON.
+    pub fn from_map(params: HashMap<String, String>) -> Self {
+        let mut builder = Self::new();
+        builder.params = params;
+        builder
+    }
+
+    /// Load connection parameters from an `sapnwrfc.ini`-style file: one `key = value`
+    /// parameter per line, blank lines and `#`/`;`-prefixed comments ignored.
+    ///
+    /// Reload the same file later and feed the result to
+    /// [`RfcConnectionPool::reload`](crate::RfcConnectionPool::reload) to pick up rotated
+    /// credentials or gateway hosts without restarting.
+    pub fn from_file<P>(path: P) -> std::result::Result<Self, ConfigError>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let contents = std::fs::read_to_string(path)?;
+        let mut params = HashMap::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                params.insert(key.trim().to_owned(), value.trim().to_owned());
+            }
+        }
+        Ok(Self::from_map(params))
+    }
+
+    /// Ping the connection at the given interval to keep it alive across SAP gateway
+    /// idle-timeouts. Combine with [`auto_reconnect`](Self::auto_reconnect) to also
+    /// reopen the connection when a keepalive ping fails.
+    pub fn keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval);
+        self
+    }
+
+    /// Transparently close and reopen the connection, using the parameters it was built
+    /// with, whenever a keepalive ping fails. Has no effect unless
+    /// [`keepalive`](Self::keepalive) is also set.
+    pub fn auto_reconnect(mut self, enabled: bool) -> Self {
+        self.auto_reconnect = enabled;
+        self
+    }
+
+    /// Register a callback invoked from the keepalive thread every time the connection is
+    /// transparently reconnected.
+    pub fn on_reconnect<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_reconnect = Some(Arc::new(callback));
+        self
+    }
+
     /// Consume the builder and try connecting with the set parameters.
     pub fn build(self) -> Result<RfcConnection> {
-        let params: Result<Vec<_>> = self
+        let params: Result<ConnParams> = self
             .params
             .into_iter()
             .map(|(k, v)| Ok((uc::from_str(&k)?, uc::from_str(&v)?)))
             .collect();
-        RfcConnection::new(params?)
+        RfcConnection::new(params?, self.keepalive, self.auto_reconnect, self.on_reconnect)
     }
 }
 
@@ -137,6 +394,16 @@ impl Default for RfcConnectionBuilder {
     }
 }
 
+impl std::fmt::Debug for RfcConnectionBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RfcConnectionBuilder")
+            .field("params", &self.params)
+            .field("keepalive", &self.keepalive)
+            .field("auto_reconnect", &self.auto_reconnect)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +438,27 @@ mod tests {
             .build()
             .unwrap_err();
     }
+
+    #[test]
+    fn keepalive_reconnect_callback_registration() {
+        // Exercises the builder plumbing without requiring a live SAP system.
+        let builder = RfcConnection::builder()
+            .set_param("dest", "TEST")
+            .keepalive(Duration::from_secs(60))
+            .auto_reconnect(true)
+            .on_reconnect(|| {});
+
+        assert_eq!(builder.keepalive, Some(Duration::from_secs(60)));
+        assert!(builder.auto_reconnect);
+        assert!(builder.on_reconnect.is_some());
+    }
+
+    #[test]
+    fn from_file_error_preserves_the_io_failure_message() {
+        let err = RfcConnectionBuilder::from_file("/no/such/sapnwrfc.ini").unwrap_err();
+        assert!(err.to_string().contains("failed to read connection config"));
+
+        let io_err = std::fs::read_to_string("/no/such/sapnwrfc.ini").unwrap_err();
+        assert!(err.to_string().contains(&io_err.to_string()));
+    }
 }
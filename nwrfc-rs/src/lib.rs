@@ -0,0 +1,18 @@
+#[cfg(feature = "async")]
+mod async_ext;
+mod client;
+mod connection;
+mod error;
+mod function;
+#[macro_use]
+mod macros;
+mod pool;
+mod server;
+mod uc;
+
+pub use client::{MockRfcConnection, RfcClient};
+pub use connection::{ConfigError, RfcConnection, RfcConnectionBuilder};
+pub use error::{Result, RfcErrorInfo};
+pub use function::RfcFunction;
+pub use pool::{PooledConnection, RfcConnectionPool};
+pub use server::{RfcServer, RfcServerBuilder};
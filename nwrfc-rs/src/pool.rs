@@ -0,0 +1,194 @@
+use crate::connection::{RfcConnection, RfcConnectionBuilder};
+use crate::error::Result;
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+/// A pool of [`RfcConnection`]s, minted from a stored [`RfcConnectionBuilder`], holding at
+/// most `max_idle` idle at a time. It is filled up to `min_idle` idle connections when
+/// created and on [`reload`](Self::reload); a checkout does not refill it, so the idle
+/// count can drop below `min_idle` while connections are checked out. Every checkout is
+/// validated with [`RfcConnection::ping`] and transparently replaced if that fails.
+#[derive(Debug)]
+pub struct RfcConnectionPool {
+    state: Mutex<PoolState>,
+    idle: Mutex<VecDeque<(u64, RfcConnection)>>,
+    min_idle: usize,
+    max_idle: usize,
+}
+
+/// The builder currently in use and a generation counter bumped by
+/// [`reload`](RfcConnectionPool::reload), so
+/// connections checked out before a reload can be told apart from current ones.
+#[derive(Debug)]
+struct PoolState {
+    builder: RfcConnectionBuilder,
+    generation: u64,
+}
+
+impl RfcConnectionPool {
+    /// Build a new pool from a connection builder, pre-filling it with `min_idle`
+    /// connections and allowing at most `max_idle` to sit idle at once.
+    pub fn new(builder: RfcConnectionBuilder, min_idle: usize, max_idle: usize) -> Result<Self> {
+        let pool = Self {
+            state: Mutex::new(PoolState {
+                builder,
+                generation: 0,
+            }),
+            idle: Mutex::new(VecDeque::with_capacity(max_idle)),
+            min_idle,
+            max_idle: max_idle.max(min_idle),
+        };
+        pool.top_up()?;
+        Ok(pool)
+    }
+
+    fn state(&self) -> (RfcConnectionBuilder, u64) {
+        let state = self.state.lock().unwrap();
+        (state.builder.clone(), state.generation)
+    }
+
+    fn top_up(&self) -> Result<()> {
+        let (builder, generation) = self.state();
+        while self.idle.lock().unwrap().len() < self.min_idle {
+            let conn = builder.clone().build()?;
+            self.idle.lock().unwrap().push_back((generation, conn));
+        }
+        Ok(())
+    }
+
+    /// Check out a connection from the pool.
+    ///
+    /// An idle connection is reused if its [`ping`](RfcConnection::ping) succeeds;
+    /// otherwise it is dropped and a fresh one is opened in its place. The returned guard
+    /// hands the connection back to the pool when it goes out of scope.
+    pub fn checkout(&self) -> Result<PooledConnection<'_>> {
+        let (builder, generation) = self.state();
+
+        let idle_conn = self.idle.lock().unwrap().pop_front();
+
+        let conn = match idle_conn {
+            Some((gen, conn)) if gen == generation && conn.ping().is_ok() => conn,
+            // The popped connection (if any) is either from a stale generation (see
+            // `reload`) or its ping failed: the old handle is dropped here and we open a
+            // fresh replacement from the current builder.
+            _ => builder.build()?,
+        };
+
+        Ok(PooledConnection {
+            pool: self,
+            generation,
+            conn: Some(conn),
+        })
+    }
+
+    fn checkin(&self, generation: u64, conn: RfcConnection) {
+        if self.state.lock().unwrap().generation != generation {
+            // Opened under a builder `reload` has since replaced: drop it instead of
+            // letting a stale-config connection back into circulation.
+            return;
+        }
+
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < self.max_idle {
+            idle.push_back((generation, conn));
+        }
+    }
+
+    /// Swap in a new connection builder (e.g. after credentials or the gateway host
+    /// rotated) for zero-downtime configuration updates.
+    ///
+    /// Idle connections opened from the old builder are dropped immediately. Connections
+    /// already checked out keep running and are tagged with the generation they were
+    /// opened under, so `checkin` drops them too instead of requeuing stale-config
+    /// connections once they're returned. `min_idle` connections are then opened from the
+    /// new builder right away.
+    pub fn reload(&self, builder: RfcConnectionBuilder) -> Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.builder = builder;
+            state.generation += 1;
+        }
+        self.idle.lock().unwrap().clear();
+        self.top_up()
+    }
+}
+
+/// An RAII guard for a connection checked out of an [`RfcConnectionPool`].
+///
+/// Derefs to the underlying [`RfcConnection`] and returns it to the pool on `Drop`.
+#[derive(Debug)]
+pub struct PooledConnection<'pool> {
+    pool: &'pool RfcConnectionPool,
+    generation: u64,
+    conn: Option<RfcConnection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = RfcConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().expect("connection taken before drop")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().expect("connection taken before drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.checkin(self.generation, conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_builder() -> RfcConnectionBuilder {
+        RfcConnection::builder().set_param("dest", "TEST")
+    }
+
+    #[test]
+    fn checkout_reuses_idle_connections() {
+        let pool = RfcConnectionPool::new(test_builder(), 1, 2).unwrap();
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+
+        drop(pool.checkout().unwrap());
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn checkin_caps_idle_connections_at_max_idle() {
+        let pool = RfcConnectionPool::new(test_builder(), 0, 1).unwrap();
+        assert_eq!(pool.idle.lock().unwrap().len(), 0);
+
+        let a = pool.checkout().unwrap();
+        let b = pool.checkout().unwrap();
+        drop(a);
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+
+        // Max idle is already full, so this one is simply dropped rather than queued.
+        drop(b);
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn checkin_drops_connections_from_a_stale_generation() {
+        let pool = RfcConnectionPool::new(test_builder(), 1, 2).unwrap();
+
+        let checked_out = pool.checkout().unwrap();
+        pool.reload(test_builder()).unwrap();
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+
+        // Returning a connection opened before the reload must not requeue it alongside
+        // the fresh, current-generation one `reload` just topped up with.
+        drop(checked_out);
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    error::{Result, RfcErrorInfo},
+    function::RfcFunction,
+    macros::*,
+    uc,
+};
+use sapnwrfc_sys::{
+    self, RfcInstallServerFunction, RfcListenAndDispatch, RfcRegisterServer, RfcShutdownServer,
+    SAP_UC,
+};
+use std::{
+    collections::HashMap,
+    ptr,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+type Handler = Box<dyn Fn(&RfcFunction) -> Result<()> + Send + Sync>;
+
+/// Server-side counterpart to [`RfcConnection`](crate::RfcConnection): registers with an
+/// SAP gateway under a program ID and dispatches inbound function module calls to
+/// handlers installed with [`install_function`](Self::install_function).
+#[derive(Debug)]
+pub struct RfcServer {
+    handle: sapnwrfc_sys::RFC_SERVER_HANDLE,
+    // Names this instance installed into the process-wide `handlers()` table, so `Drop`
+    // can remove exactly its own entries instead of leaking them for the process lifetime.
+    installed: Mutex<Vec<String>>,
+}
+
+impl RfcServer {
+    fn new(params: Vec<(Vec<SAP_UC>, Vec<SAP_UC>)>) -> Result<RfcServer> {
+        let conn_params: Vec<_> = params
+            .iter()
+            .map(|(k, v)| sapnwrfc_sys::RFC_CONNECTION_PARAMETER {
+                name: k.as_ptr(),
+                value: v.as_ptr(),
+            })
+            .collect();
+
+        let mut err_info = RfcErrorInfo::new();
+        let handle = unsafe {
+            RfcRegisterServer(
+                conn_params.as_ptr(),
+                conn_params.len() as u32,
+                err_info.as_mut_ptr(),
+            )
+        };
+        if handle.is_null() {
+            return Err(err_info);
+        }
+        Ok(Self {
+            handle,
+            installed: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Get an empty server builder to provide the gateway/program-id parameters.
+    pub fn builder() -> RfcServerBuilder {
+        RfcServerBuilder::default()
+    }
+
+    /// Install a handler for a function module name.
+    ///
+    /// The handler is called with an [`RfcFunction`] whose import parameters are already
+    /// populated; it fills in the export parameters and returns `Ok(())`, or an `Err` to
+    /// have it reported back to the calling ABAP system as an RFC error.
+    pub fn install_function<F>(&self, name: &str, handler: F) -> Result<()>
+    where
+        F: Fn(&RfcFunction) -> Result<()> + Send + Sync + 'static,
+    {
+        let uc_name = uc::from_str(name)?;
+
+        let mut err_info = RfcErrorInfo::new();
+        unsafe {
+            check_rc_ok!(RfcInstallServerFunction(
+                ptr::null_mut(),
+                uc_name.as_ptr(),
+                dispatch,
+                err_info.as_mut_ptr(),
+            ));
+        }
+
+        handlers()
+            .lock()
+            .unwrap()
+            .insert(name.to_owned(), Arc::new(Box::new(handler)));
+        self.installed.lock().unwrap().push(name.to_owned());
+        Ok(())
+    }
+
+    /// Block the calling thread, dispatching inbound calls to their installed handlers
+    /// until the server is shut down or an unrecoverable error occurs.
+    pub fn listen_and_dispatch(&self) -> Result<()> {
+        loop {
+            let mut err_info = RfcErrorInfo::new();
+            unsafe {
+                check_rc_ok!(RfcListenAndDispatch(self.handle, 0, err_info.as_mut_ptr()));
+            }
+        }
+    }
+}
+
+unsafe impl Send for RfcServer {}
+unsafe impl Sync for RfcServer {}
+
+impl Drop for RfcServer {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            let mut err_info = RfcErrorInfo::new();
+            unsafe {
+                if is_rc_err!(RfcShutdownServer(self.handle, 0, err_info.as_mut_ptr())) {
+                    log::warn!("Server shutdown failed: {}", err_info);
+                }
+            }
+            self.handle = ptr::null_mut();
+        }
+
+        // Deregister this instance's handlers so a dropped server doesn't leak its
+        // captured state, and so a later server reusing the same function names doesn't
+        // silently inherit this one's closures.
+        let mut table = handlers().lock().unwrap();
+        for name in self.installed.get_mut().unwrap().drain(..) {
+            table.remove(&name);
+        }
+    }
+}
+
+fn handlers() -> &'static Mutex<HashMap<String, Arc<Handler>>> {
+    static HANDLERS: OnceLock<Mutex<HashMap<String, Arc<Handler>>>> = OnceLock::new();
+    HANDLERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Trampoline registered with the NW RFC SDK for every installed function; looks the
+/// handler up by the function module's own name and runs it against the inbound call.
+unsafe extern "C" fn dispatch(
+    func_handle: sapnwrfc_sys::RFC_FUNCTION_HANDLE,
+    _err_info: *mut sapnwrfc_sys::RFC_ERROR_INFO,
+) -> sapnwrfc_sys::RFC_RC {
+    let name = match uc::function_name(func_handle) {
+        Ok(name) => name,
+        Err(err) => {
+            log::error!("Failed to resolve dispatched function name: {}", err);
+            return sapnwrfc_sys::RFC_RC::RFC_EXTERNAL_FAILURE;
+        }
+    };
+
+    // Clone the handler out and drop the registry lock before running it, so a handler
+    // that happens to install another function doesn't deadlock.
+    let handler = handlers().lock().unwrap().get(&name).cloned();
+    let func = RfcFunction::from_server_handle(func_handle);
+
+    match handler {
+        Some(handler) => match handler(&func) {
+            Ok(()) => sapnwrfc_sys::RFC_RC::RFC_OK,
+            Err(err) => {
+                log::error!("Handler for `{}` failed: {}", name, err);
+                sapnwrfc_sys::RFC_RC::RFC_EXTERNAL_FAILURE
+            }
+        },
+        None => {
+            log::error!("No handler installed for `{}`", name);
+            sapnwrfc_sys::RFC_RC::RFC_EXTERNAL_FAILURE
+        }
+    }
+}
+
+/// A builder for the gateway/program-id parameters an [`RfcServer`] registers with.
+#[derive(Clone, Debug, Default)]
+pub struct RfcServerBuilder {
+    params: HashMap<String, String>,
+}
+
+impl RfcServerBuilder {
+    /// Get a new, empty, builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter to a given value (e.g. `"GWHOST"`, `"GWSERV"`, `"PROGRAM_ID"`).
+    pub fn set_param<T>(mut self, key: &str, value: T) -> Self
+    where
+        T: ToString,
+    {
+        self.params.insert(key.to_owned(), value.to_string());
+        self
+    }
+
+    /// Consume the builder and register the server with the set parameters.
+    pub fn build(self) -> Result<RfcServer> {
+        let params: Result<Vec<_>> = self
+            .params
+            .into_iter()
+            .map(|(k, v)| Ok((uc::from_str(&k)?, uc::from_str(&v)?)))
+            .collect();
+        RfcServer::new(params?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_param_stores_the_given_value() {
+        let builder = RfcServer::builder()
+            .set_param("GWHOST", "sapgw00")
+            .set_param("PROGRAM_ID", "RUST_SERVER");
+
+        assert_eq!(builder.params.get("GWHOST").map(String::as_str), Some("sapgw00"));
+        assert_eq!(
+            builder.params.get("PROGRAM_ID").map(String::as_str),
+            Some("RUST_SERVER")
+        );
+    }
+
+    #[test]
+    fn builder_defaults_to_no_parameters() {
+        assert!(RfcServerBuilder::new().params.is_empty());
+        assert!(RfcServerBuilder::default().params.is_empty());
+    }
+
+    // Requires a live gateway to register against, same as `connection`'s own
+    // `smoke_test`/`negative_smoke_test`.
+    #[test]
+    fn install_function_registers_in_the_dispatch_table() {
+        let server = RfcServer::builder()
+            .set_param("GWHOST", "sapgw00")
+            .set_param("PROGRAM_ID", "RUST_SERVER_TEST")
+            .build()
+            .unwrap();
+
+        server
+            .install_function("ZRUST_TEST_ECHO", |_| Ok(()))
+            .unwrap();
+
+        assert!(handlers().lock().unwrap().contains_key("ZRUST_TEST_ECHO"));
+    }
+
+    #[test]
+    fn drop_deregisters_this_servers_installed_functions() {
+        let server = RfcServer::builder()
+            .set_param("GWHOST", "sapgw00")
+            .set_param("PROGRAM_ID", "RUST_SERVER_TEST")
+            .build()
+            .unwrap();
+
+        server
+            .install_function("ZRUST_TEST_ECHO", |_| Ok(()))
+            .unwrap();
+        drop(server);
+
+        assert!(!handlers().lock().unwrap().contains_key("ZRUST_TEST_ECHO"));
+    }
+}